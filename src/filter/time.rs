@@ -1,12 +1,13 @@
-use chrono::{naive::NaiveDateTime, offset::TimeZone, DateTime, Local};
+use chrono::{naive::NaiveDateTime, offset::TimeZone, DateTime, FixedOffset, Local};
 
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Filter based on time ranges.
 #[derive(Debug, PartialEq)]
 pub enum TimeFilter {
     Before(SystemTime),
     After(SystemTime),
+    Between(SystemTime, SystemTime),
 }
 
 impl TimeFilter {
@@ -14,6 +15,7 @@ impl TimeFilter {
         humantime::parse_duration(s)
             .map(|duration| *ref_time - duration)
             .ok()
+            .or_else(|| from_offset_datetime(s))
             .or_else(|| {
                 humantime::parse_rfc3339_weak(s)
                     .or_else(|_| humantime::parse_rfc3339_weak(&(s.to_owned() + " 00:00:00")))
@@ -30,39 +32,70 @@ impl TimeFilter {
         TimeFilter::from_str(ref_time, s).map(TimeFilter::After)
     }
 
+    pub fn between(ref_time: &SystemTime, first: &str, second: &str) -> Option<TimeFilter> {
+        let a = TimeFilter::from_str(ref_time, first)?;
+        let b = TimeFilter::from_str(ref_time, second)?;
+
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        Some(TimeFilter::Between(lower, upper))
+    }
+
     pub fn applies_to(&self, t: &SystemTime) -> bool {
         match self {
             TimeFilter::Before(limit) => t <= limit,
             TimeFilter::After(limit) => t >= limit,
+            TimeFilter::Between(lower, upper) => lower <= t && t <= upper,
         }
     }
 }
 
+/// Parse an absolute date/time string that carries an explicit timezone
+/// offset, honoring that offset rather than reinterpreting it in the local
+/// zone. Returns `None` if `s` has no recognizable offset, so callers can
+/// fall back to the existing humantime-based parsing.
+fn from_offset_datetime(s: &str) -> Option<SystemTime> {
+    DateTime::<FixedOffset>::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::<FixedOffset>::parse_from_str(s, "%Y-%m-%d %H:%M:%S%z"))
+        .ok()
+        .map(SystemTime::from)
+}
+
 /// The humantime `parse_rfc3339_weak` function returns a UTC-based SystemTime,
 /// the following is to convert to a local SystemTime
 fn to_local_system_time(system_time: SystemTime) -> Option<SystemTime> {
-    // convert to duration since epoch
-    system_time
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .ok()
-        .and_then(|from_epoch| {
-            // convert to local datetime
-            Local
-                .from_local_datetime(&NaiveDateTime::from_timestamp(
-                    from_epoch.as_secs() as _,
-                    from_epoch.subsec_nanos(),
-                ))
-                .single()
-        })
+    // convert to (seconds, nanoseconds) since epoch, allowing for times before
+    // the epoch (negative seconds)
+    let (secs, nsec) = match system_time.duration_since(UNIX_EPOCH) {
+        Ok(dur) => (dur.as_secs() as i64, dur.subsec_nanos()),
+        Err(e) => {
+            let neg = e.duration();
+            if neg.subsec_nanos() == 0 {
+                (-(neg.as_secs() as i64), 0)
+            } else {
+                (-(neg.as_secs() as i64) - 1, 1_000_000_000 - neg.subsec_nanos())
+            }
+        }
+    };
+
+    // convert to local datetime
+    NaiveDateTime::from_timestamp_opt(secs, nsec)
+        .and_then(|naive| Local.from_local_datetime(&naive).single())
         .and_then(|local_time| {
-            // convert adjusted time back to SystemTime
+            // convert adjusted time back to SystemTime, which may be before the epoch
             let local_epoch: DateTime<Local> = DateTime::from(SystemTime::UNIX_EPOCH);
+            let offset = local_time.signed_duration_since(local_epoch);
 
-            local_time
-                .signed_duration_since(local_epoch)
-                .to_std()
-                .ok()
-                .map(|duration| SystemTime::UNIX_EPOCH + duration)
+            if offset < chrono::Duration::zero() {
+                (-offset)
+                    .to_std()
+                    .ok()
+                    .and_then(|duration| SystemTime::UNIX_EPOCH.checked_sub(duration))
+            } else {
+                offset
+                    .to_std()
+                    .ok()
+                    .map(|duration| SystemTime::UNIX_EPOCH + duration)
+            }
         })
 }
 
@@ -114,4 +147,74 @@ mod tests {
             .unwrap()
             .applies_to(&t1m_ago));
     }
+
+    #[test]
+    fn to_local_system_time_handles_pre_epoch() {
+        // 1969-01-01T00:00:00Z, one year before the epoch
+        let pre_epoch = UNIX_EPOCH - Duration::from_secs(365 * 24 * 60 * 60);
+
+        let converted = to_local_system_time(pre_epoch).unwrap();
+        assert!(converted < UNIX_EPOCH);
+
+        // round-tripping should keep ordering monotonic relative to the epoch
+        let converted_epoch = to_local_system_time(UNIX_EPOCH).unwrap();
+        assert!(converted < converted_epoch);
+
+        // a time a second later should still convert to something later
+        let pre_epoch_plus_1s = pre_epoch + Duration::from_secs(1);
+        let converted_plus_1s = to_local_system_time(pre_epoch_plus_1s).unwrap();
+        assert!(converted_plus_1s > converted);
+    }
+
+    #[test]
+    fn to_local_system_time_rejects_out_of_range_timestamp() {
+        // far beyond the range NaiveDateTime can represent; must not panic
+        let absurd = UNIX_EPOCH + Duration::from_secs(u64::MAX / 2);
+
+        assert_eq!(to_local_system_time(absurd), None);
+    }
+
+    #[test]
+    fn from_str_honors_explicit_offset() {
+        // 10:10:10+02:00 is 08:10:10 UTC, i.e. two hours before the UTC
+        // instant a naive reinterpretation of the same clock time would give
+        let expected: SystemTime =
+            DateTime::<FixedOffset>::parse_from_rfc3339("2010-10-10T10:10:10+02:00")
+                .unwrap()
+                .into();
+        let naive_utc_reading = humantime::parse_rfc3339("2010-10-10T10:10:10Z").unwrap();
+        assert_ne!(expected, naive_utc_reading);
+
+        assert_eq!(
+            TimeFilter::from_str(&expected, "2010-10-10T10:10:10+02:00"),
+            Some(expected)
+        );
+        assert_eq!(
+            from_offset_datetime("2010-10-10 10:10:10+0200"),
+            Some(expected)
+        );
+        assert_eq!(from_offset_datetime("not a date"), None);
+    }
+
+    #[test]
+    fn between_applies_to_window() {
+        let ref_time =
+            to_local_system_time(humantime::parse_rfc3339("2010-10-10T10:10:10Z").unwrap())
+                .unwrap();
+
+        let filter = TimeFilter::between(&ref_time, "1min", "2min").unwrap();
+
+        let t90s_ago = ref_time - Duration::from_secs(90);
+        assert!(filter.applies_to(&t90s_ago));
+
+        let t30s_ago = ref_time - Duration::from_secs(30);
+        assert!(!filter.applies_to(&t30s_ago));
+
+        let t3m_ago = ref_time - Duration::from_secs(180);
+        assert!(!filter.applies_to(&t3m_ago));
+
+        // arguments given in either order produce the same window
+        let reversed = TimeFilter::between(&ref_time, "2min", "1min").unwrap();
+        assert_eq!(filter, reversed);
+    }
 }